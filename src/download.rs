@@ -0,0 +1,167 @@
+//! Downloads TIGER/Line shapefile archives from census.gov, with an on-disk cache
+//! keyed by geography/state/year, mirroring the caching model of the R `tigris` package.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use thiserror::Error;
+use zip::ZipArchive;
+
+/// Environment variable overriding the default cache directory.
+const CACHE_DIR_ENV_VAR: &str = "CIVICSEARCH_CACHE_DIR";
+
+/// Geographies published as a single nationwide archive rather than one per state.
+const NATIONAL_GEOGRAPHIES: &[&str] = &["state", "county"];
+
+/// Errors that can occur when downloading or caching a TIGER shapefile archive.
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    /// A geography that requires a state FIPS code (e.g. `sldl`) was requested without one.
+    #[error("geography '{geography:}' requires a --state FIPS code")]
+    MissingState {
+        geography: String,
+    },
+
+    /// There was a problem reaching census.gov or reading its response.
+    #[error("error downloading TIGER archive")]
+    Request(#[from] reqwest::Error),
+
+    /// There was a problem reading from or writing to the cache directory.
+    #[error("IO error reading/writing TIGER archive cache")]
+    Io(#[from] std::io::Error),
+
+    /// The downloaded (or cached) bytes weren't a valid zip archive.
+    #[error("error reading downloaded zip archive")]
+    ZipFile(#[from] zip::result::ZipError),
+}
+
+/// Returns the directory TIGER archives are cached in, honoring `CIVICSEARCH_CACHE_DIR`
+/// and otherwise falling back to the platform's standard cache directory.
+fn cache_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var(CACHE_DIR_ENV_VAR) {
+        return PathBuf::from(dir);
+    }
+
+    directories::ProjectDirs::from("", "", "civicsearch")
+        .map(|dirs| dirs.cache_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from(".civicsearch-cache"))
+}
+
+/// Zero-pads a state FIPS code to TIGER's canonical 2-digit width (e.g. `"6"` -> `"06"`
+/// for California), so `--state 6` resolves the same archive as `--state 06`.
+fn normalize_state_fips(state_fips: &str) -> String {
+    format!("{:0>2}", state_fips)
+}
+
+/// Resolves the geography token TIGER actually uses in a filename, handling `tabblock`'s
+/// vintage suffix: block files are published as `tabblock10` for 2011-2019 vintages and
+/// `tabblock20` for 2020 onward, rather than bare `tabblock`. Every other geography's
+/// filename token is just its name.
+fn geography_filename_component(geography: &str, year: u32) -> String {
+    if geography == "tabblock" {
+        String::from(if year >= 2020 { "tabblock20" } else { "tabblock10" })
+    } else {
+        String::from(geography)
+    }
+}
+
+/// Builds the canonical TIGER URL for a geography/state/year combination, per
+/// [section 2 of the TIGER Shapefile technical document](https://www2.census.gov/geo/pdfs/maps-data/data/tiger/tgrshp2019/TGRSHP2019_TechDoc.pdf).
+///
+/// Nationwide geographies (e.g. `state`, `county`) live directly under the geography's
+/// folder as a single `tl_{year}_us_{geography}.zip`; state-based geographies (e.g.
+/// `sldl`, `sldu`, `tabblock`) are one file per state, named with the state's FIPS code.
+///
+/// # Examples
+/// ```
+/// // A nationwide geography needs no --state.
+/// assert_eq!(
+///     civicsearch::download::tiger_url("state", None, 2019).unwrap(),
+///     "https://www2.census.gov/geo/tiger/TIGER2019/STATE/tl_2019_us_state.zip",
+/// );
+///
+/// // A state-keyed geography's FIPS code is zero-padded, whether or not it was passed that way.
+/// assert_eq!(
+///     civicsearch::download::tiger_url("sldl", Some("6"), 2019).unwrap(),
+///     "https://www2.census.gov/geo/tiger/TIGER2019/SLDL/tl_2019_06_sldl.zip",
+/// );
+/// assert_eq!(
+///     civicsearch::download::tiger_url("sldl", Some("06"), 2019).unwrap(),
+///     "https://www2.census.gov/geo/tiger/TIGER2019/SLDL/tl_2019_06_sldl.zip",
+/// );
+/// ```
+pub fn tiger_url(geography: &str, state_fips: Option<&str>, year: u32) -> Result<String, DownloadError> {
+    let folder = geography.to_uppercase();
+    let geography_component = geography_filename_component(geography, year);
+
+    if NATIONAL_GEOGRAPHIES.contains(&geography) {
+        Ok(format!("https://www2.census.gov/geo/tiger/TIGER{year}/{folder}/tl_{year}_us_{geography_component}.zip"))
+    } else {
+        let state_fips = state_fips
+            .ok_or_else(|| DownloadError::MissingState { geography: String::from(geography) })?;
+        let state_fips = normalize_state_fips(state_fips);
+        Ok(format!("https://www2.census.gov/geo/tiger/TIGER{year}/{folder}/tl_{year}_{state_fips}_{geography_component}.zip"))
+    }
+}
+
+/// Path a given geography/state/year archive is (or would be) cached at.
+///
+/// # Examples
+/// ```
+/// use std::env;
+///
+/// env::set_var("CIVICSEARCH_CACHE_DIR", "/tmp/civicsearch-doctest-cache");
+///
+/// // A national geography is cached as one file, with no state FIPS segment.
+/// assert_eq!(
+///     civicsearch::download::cache_path("state", None, 2019),
+///     std::path::Path::new("/tmp/civicsearch-doctest-cache/tl_2019_us_state.zip"),
+/// );
+///
+/// // A state-keyed geography's FIPS code is zero-padded, whether or not it was passed that way.
+/// assert_eq!(
+///     civicsearch::download::cache_path("sldl", Some("6"), 2019),
+///     std::path::Path::new("/tmp/civicsearch-doctest-cache/tl_2019_06_sldl.zip"),
+/// );
+/// assert_eq!(
+///     civicsearch::download::cache_path("sldl", Some("06"), 2019),
+///     std::path::Path::new("/tmp/civicsearch-doctest-cache/tl_2019_06_sldl.zip"),
+/// );
+/// ```
+pub fn cache_path(geography: &str, state_fips: Option<&str>, year: u32) -> PathBuf {
+    let geography_component = geography_filename_component(geography, year);
+    let filename = match state_fips {
+        Some(fips) if !NATIONAL_GEOGRAPHIES.contains(&geography) => format!("tl_{year}_{}_{geography_component}.zip", normalize_state_fips(fips)),
+        _ => format!("tl_{year}_us_{geography_component}.zip"),
+    };
+    cache_dir().join(filename)
+}
+
+/// Fetches a TIGER shapefile archive for the given geography/state/year, reusing the
+/// on-disk cache if it's already been downloaded, and returns a `ZipArchive` ready for
+/// [`crate::shapefile_reader_from_zip_archive`].
+pub fn fetch_tiger_archive(geography: &str, state_fips: Option<&str>, year: u32) -> Result<ZipArchive<Cursor<Vec<u8>>>, DownloadError> {
+    let path = cache_path(geography, state_fips, year);
+
+    let bytes = if path.exists() {
+        log::debug!("Using cached TIGER archive at {}", path.display());
+        fs::read(&path)?
+    } else {
+        let url = tiger_url(geography, state_fips, year)?;
+        log::info!("Downloading TIGER archive from {}", url);
+        let bytes = reqwest::blocking::get(&url)?
+            .error_for_status()?
+            .bytes()?
+            .to_vec();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &bytes)?;
+
+        bytes
+    };
+
+    Ok(ZipArchive::new(Cursor::new(bytes))?)
+}
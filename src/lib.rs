@@ -4,14 +4,28 @@ use shapefile::Reader;
 use shapefile::dbase::FieldValue;
 use shapefile::Polygon;
 use geo::algorithm::contains::Contains;
+use geo::algorithm::bounding_rect::BoundingRect;
+use geo::algorithm::closest_point::ClosestPoint;
+use geo::algorithm::haversine_distance::HaversineDistance;
+use geo::Closest;
 use zip::{ZipArchive, read::ZipFile};
 use std::path::Path;
 use std::ffi::OsStr;
 
-use log::{debug, info};
+use log::{debug, info, warn};
 
 use thiserror::Error;
 
+use proj::Proj;
+
+use rstar::{RTree, RTreeObject, AABB, PointDistance};
+
+pub mod download;
+pub mod output;
+
+/// The CRS user-supplied coordinates (e.g. a lat/lon CSV) are assumed to be in.
+const WGS84_EPSG: &str = "EPSG:4326";
+
 /// Errors that can occur when reading TIGER shapefiles
 #[derive(Error, Debug)]
 pub enum TIGERShapefileError {
@@ -21,7 +35,7 @@ pub enum TIGERShapefileError {
     MissingFile {
         extension: String,
     },
-    
+
     /// There are multiple `.shp`, `.dbf`, or `.shx` files in the TIGER shapefile `.zip` archive (there should only be one of each)
     #[error("too many .{extension:} files in archive")]
     TooManyFiles {
@@ -39,6 +53,14 @@ pub enum TIGERShapefileError {
     /// There was some kind of IO error (likely in `.zip` extraction)
     #[error("IO Error")]
     Io(#[from] std::io::Error),
+
+    /// Could not build a coordinate transformation from the `.prj` file's CRS
+    #[error("error setting up CRS reprojection")]
+    ProjCreate(#[from] proj::ProjCreateError),
+
+    /// Could not reproject a point into the shapefile's CRS
+    #[error("error reprojecting point")]
+    ProjTransform(#[from] proj::ProjError),
 }
 
 /// Checks if a point is in a shape.
@@ -99,38 +121,35 @@ pub fn shape_contains_point(shape: &Polygon, point: &geo_types::Point<f64>) -> b
     shape.contains(point)
 }
 
-/// Searches a shape's record to find the name of a district.
-/// 
-/// For TIGER shapefiles, district names are stored in the "NAMELSAD" field 
-/// in the `.dbf` database stored with a .shp shapefile.
-/// 
+/// Searches a shape's record for the values of a list of requested `.dbf` field names.
+///
+/// Different TIGER products key their identifying column differently (`NAMELSAD` is
+/// just the convention for most of them), and most downstream joins need the stable
+/// `GEOID`/`GEOIDFQ` code rather than a display name, so the caller picks which fields
+/// it wants. Returns one entry per name in `fields`, in the same order; an entry is
+/// `None` if the field isn't present in the record or isn't a `Character` value.
+///
 /// # Examples
 /// ```
 /// use std::collections::HashMap;
 /// use shapefile::dbase::FieldValue;
-/// 
+///
 /// let mut record = HashMap::new();
-/// 
-/// record.insert(String::from("NAMELSAD"), 
-///     FieldValue::Character(
-///         Some(String::from("1st District")))
-/// );
-/// 
-/// assert_eq!(civicsearch::extract_district_name(record), Some(String::from("1st District")));
+/// record.insert(String::from("NAMELSAD"), FieldValue::Character(Some(String::from("1st District"))));
+/// record.insert(String::from("GEOID"), FieldValue::Character(Some(String::from("2501"))));
+///
+/// let fields = vec![String::from("NAMELSAD"), String::from("GEOID"), String::from("STATEFP")];
+/// let values = civicsearch::extract_fields(&record, &fields);
+///
+/// assert_eq!(values, vec![Some(String::from("1st District")), Some(String::from("2501")), None]);
 /// ```
-/// 
-/// ```
-/// # use std::collections::HashMap;
-/// 
-/// let mut empty_record = HashMap::new();
-/// 
-/// assert_eq!(civicsearch::extract_district_name(empty_record), None);
-/// ```
-pub fn extract_district_name(record: HashMap<String, FieldValue>) -> Option<String> {
-    match record.get("NAMELSAD") {
-        Some(FieldValue::Character(Some(n))) => Some(n.clone()),
-        _ => None, 
-    }
+pub fn extract_fields(record: &HashMap<String, FieldValue>, fields: &[String]) -> Vec<Option<String>> {
+    fields.iter()
+        .map(|field| match record.get(field) {
+            Some(FieldValue::Character(Some(value))) => Some(value.clone()),
+            _ => None,
+        })
+        .collect()
 }
 
 /// Stores the filenames of relevant data within a TIGER shapefile archive
@@ -138,6 +157,9 @@ struct TIGERShapefileArchiveFilenames<'a> {
     shp_filename: &'a str,
     dbf_filename: &'a str,
     shx_filename: &'a str,
+    /// The `.prj` file holding the archive's CRS as WKT. Unlike the other three files,
+    /// this one is optional: older or third-party TIGER redistributions sometimes omit it.
+    prj_filename: Option<&'a str>,
 }
 
 /// 
@@ -161,13 +183,13 @@ fn find_file_in_zipfile_by_extension<'a, R>(zip_archive: &'a ZipArchive<R>, exte
 }
 
 /// Searches in the zipped TIGER shapefile for relevant files by extension
-/// 
-/// There are three files we need from the zipped TIGER shapefile - 
+///
+/// There are three files we need from the zipped TIGER shapefile -
 /// (cf [section 2.2.1 of the TIGER Shapefile technical document](https://www2.census.gov/geo/pdfs/maps-data/data/tiger/tgrshp2019/TGRSHP2019_TechDoc.pdf))
 /// * .shp - the feature geometry
 /// * .shx - the index of the feature geometry
 /// * .dbf - the tabular attribute information
-fn get_shapefile_names_from_tiger_zipfile<R>(zip_archive: &ZipArchive<R>) -> Result<TIGERShapefileArchiveFilenames, TIGERShapefileError> 
+fn get_shapefile_names_from_tiger_zipfile<R>(zip_archive: &ZipArchive<R>) -> Result<TIGERShapefileArchiveFilenames, TIGERShapefileError>
     where R: Read + Seek,
 {
     Ok(TIGERShapefileArchiveFilenames{
@@ -177,36 +199,115 @@ fn get_shapefile_names_from_tiger_zipfile<R>(zip_archive: &ZipArchive<R>) -> Res
             .ok_or(TIGERShapefileError::MissingFile { extension: String::from("dbf")})?,
         shx_filename: find_file_in_zipfile_by_extension(zip_archive, "shx")?
         .ok_or(TIGERShapefileError::MissingFile { extension: String::from("shx")})?,
+        prj_filename: find_file_in_zipfile_by_extension(zip_archive, "prj")?,
     })
 }
 
+/// Finds the single nested `.zip` entry in a "zip-of-zips" TIGER distribution, if any.
+///
+/// Some providers ship a single outer `.zip` that contains one inner `.zip` per shapefile
+/// rather than loose `.shp`/`.dbf`/`.shx` entries. Returns `Ok(None)` if there is no `.zip`
+/// entry at all (a genuinely malformed or unrelated archive), and an error if there's more
+/// than one, since we wouldn't know which inner archive to use.
+fn find_nested_zipfile_name<R>(zip_archive: &ZipArchive<R>) -> Result<Option<String>, TIGERShapefileError>
+    where R: Read + Seek,
+{
+    let nested_zip_names = zip_archive.file_names()
+        .filter(|f| {
+            Path::new(f)
+                .extension()
+                .and_then(|x| { Some(OsStr::to_string_lossy(x)) })
+                .map_or(false, |x| { x == "zip" })
+        })
+        .map(String::from)
+        .collect::<Vec<String>>();
+
+    if nested_zip_names.len() > 1 {
+        return Err(TIGERShapefileError::TooManyFiles { extension: String::from("zip") })
+    }
+
+    Ok(nested_zip_names.into_iter().next())
+}
+
 /// Represents the actual files (or file-like objects) from a zipped TIGER shapefile
-struct TIGERShapefileArchive<T> 
+struct TIGERShapefileArchive<T>
 where T: Read,
 {
     shape_file:      T,
     db_file:         T,
     shapeindex_file: T,
+    /// The WKT contents of the archive's `.prj` file, or `None` if it didn't have one.
+    crs_wkt:         Option<String>,
 }
 
-fn extract_file_in_memory(mut zip_file: ZipFile) -> Result<Cursor<Vec<u8>>, TIGERShapefileError> 
+fn extract_file_in_memory(mut zip_file: ZipFile) -> Result<Cursor<Vec<u8>>, TIGERShapefileError>
 {
     let mut output_buffer = Vec::new();
     zip_file.read_to_end(&mut output_buffer)?;
     Ok(Cursor::new(output_buffer))
 }
 
+fn extract_file_as_string(mut zip_file: ZipFile) -> Result<String, TIGERShapefileError>
+{
+    let mut contents = String::new();
+    zip_file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Reads the three shapefile components out of an already-opened `ZipArchive`.
+///
+/// Handles both the common flat layout (`.shp`/`.dbf`/`.shx` at the top level of the
+/// archive) and the nested "zip-of-zips" layout some providers use, where the outer
+/// archive contains a single inner `.zip` holding the actual shapefile.
 fn extract_shapefiles<'a, R: 'a>(zip_archive: ZipArchive<R>) -> Result<TIGERShapefileArchive<Cursor<Vec<u8>>>, TIGERShapefileError>
     where R: Clone + Read + Seek,
 {
     info!("Checking for Files in Archive");
-    let shapefile_names = get_shapefile_names_from_tiger_zipfile(&zip_archive)?;
+    match get_shapefile_names_from_tiger_zipfile(&zip_archive) {
+        Ok(shapefile_names) => Ok(
+            TIGERShapefileArchive {
+                shape_file:      extract_file_in_memory(zip_archive.clone().by_name(shapefile_names.shp_filename)?)?,
+                db_file:         extract_file_in_memory(zip_archive.clone().by_name(shapefile_names.dbf_filename)?)?,
+                shapeindex_file: extract_file_in_memory(zip_archive.clone().by_name(shapefile_names.shx_filename)?)?,
+                crs_wkt:         shapefile_names.prj_filename
+                    .map(|n| extract_file_as_string(zip_archive.clone().by_name(n)?))
+                    .transpose()?,
+            }
+        ),
+        Err(TIGERShapefileError::MissingFile { extension }) if extension == "shp" => {
+            debug!("No top-level .shp found; checking for a nested zip-of-zips archive");
+            extract_shapefiles_from_nested_zip(zip_archive)
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Extracts shapefile components from a "zip-of-zips" archive: an outer `.zip` whose
+/// sole relevant entry is itself a `.zip` containing the `.shp`/`.dbf`/`.shx` triplet.
+///
+/// Returns [`TIGERShapefileError::MissingFile`] if the outer archive has no nested
+/// `.zip` either, and [`TIGERShapefileError::TooManyFiles`] if the inner archive
+/// contains more than one shapefile.
+fn extract_shapefiles_from_nested_zip<R>(mut zip_archive: ZipArchive<R>) -> Result<TIGERShapefileArchive<Cursor<Vec<u8>>>, TIGERShapefileError>
+    where R: Read + Seek,
+{
+    let nested_zip_name = find_nested_zipfile_name(&zip_archive)?
+        .ok_or(TIGERShapefileError::MissingFile { extension: String::from("shp") })?;
+
+    debug!("Reading nested archive {} into memory", nested_zip_name);
+    let nested_zip_buffer = extract_file_in_memory(zip_archive.by_name(&nested_zip_name)?)?;
+    let nested_zip_archive = ZipArchive::new(nested_zip_buffer)?;
+
+    let shapefile_names = get_shapefile_names_from_tiger_zipfile(&nested_zip_archive)?;
 
     Ok(
         TIGERShapefileArchive {
-            shape_file:      extract_file_in_memory(zip_archive.clone().by_name(shapefile_names.shp_filename)?)?,
-            db_file:         extract_file_in_memory(zip_archive.clone().by_name(shapefile_names.dbf_filename)?)?,
-            shapeindex_file: extract_file_in_memory(zip_archive.clone().by_name(shapefile_names.shx_filename)?)?,
+            shape_file:      extract_file_in_memory(nested_zip_archive.clone().by_name(shapefile_names.shp_filename)?)?,
+            db_file:         extract_file_in_memory(nested_zip_archive.clone().by_name(shapefile_names.dbf_filename)?)?,
+            shapeindex_file: extract_file_in_memory(nested_zip_archive.clone().by_name(shapefile_names.shx_filename)?)?,
+            crs_wkt:         shapefile_names.prj_filename
+                .map(|n| extract_file_as_string(nested_zip_archive.clone().by_name(n)?))
+                .transpose()?,
         }
     )
 }
@@ -227,19 +328,62 @@ fn extract_shapefiles<'a, R: 'a>(zip_archive: ZipArchive<R>) -> Result<TIGERShap
 /// |- tl_2019_25_sldl.shp.ea.iso.xml
 /// |- tl_2019_25_sldl.shp.iso.xml
 /// ```
-/// We need three of these files, the `.shp`, the `.dbf`, and the `.shx`, 
+/// We need three of these files, the `.shp`, the `.dbf`, and the `.shx`,
 /// so it is probably more user-friendly to look for a single file, versus asking for three.
 /// This function builds a `shapefile::Reader` from a zip file containing those three files.
-/// 
-/// 
+///
+/// If the archive also contains a `.prj` file, its WKT contents are returned alongside
+/// the reader as the shapefile's source CRS, for use by [`find_districts_for_points`]
+/// when reprojecting input points. TIGER shapefiles are normally in NAD83 (EPSG:4269),
+/// but no CRS is assumed: the WKT is read directly from the archive,
+/// since not every TIGER-derived distribution uses the same CRS.
+///
+/// If the top-level archive doesn't contain a `.shp` directly, this also handles the
+/// "zip-of-zips" layout some providers use, where the outer archive's only relevant
+/// entry is itself a `.zip` holding the `.shp`/`.dbf`/`.shx` triplet.
+///
 /// # Errors
-/// This function will return an error if: 
-/// * One of the 3 required files is not present in the `.zip` archive
-/// * There are multiple `.shp`, `.dbf`, or `.shx` files in the archive
+/// This function will return an error if:
+/// * One of the 3 required files is not present in the `.zip` archive (directly, or in a
+///   single nested `.zip` entry)
+/// * There are multiple `.shp`, `.dbf`, or `.shx` files in the archive, or multiple nested `.zip` entries
 /// * There is an error opening the zip file (see errors in the `zip` crate for more details)
 /// * There is an error parsing the `.shp`, `.dbf`, or `.shx` files (see `shapefile::Error` for more details)
-/// 
-pub fn shapefile_reader_from_zip_archive<R>(zip_archive: ZipArchive<R>) -> Result<Reader<impl Read>, TIGERShapefileError> 
+///
+/// # Examples
+/// A "zip-of-zips" archive: the outer `.zip`'s only entry is itself a `.zip` containing
+/// the `.shp`/`.dbf`/`.shx` triplet. The entries here aren't real shapefile data, so
+/// parsing eventually fails once they're found — this just confirms the nested archive
+/// is located and its files handed off to the shapefile parser, rather than reported missing:
+/// ```
+/// use std::io::{Cursor, Write};
+/// use zip::write::{ZipWriter, FileOptions};
+/// use zip::ZipArchive;
+///
+/// let mut inner_buffer = Vec::new();
+/// {
+///     let mut inner_zip = ZipWriter::new(Cursor::new(&mut inner_buffer));
+///     let options = FileOptions::default();
+///     inner_zip.start_file("tl_2019_25_sldl.shp", options).unwrap();
+///     inner_zip.start_file("tl_2019_25_sldl.dbf", options).unwrap();
+///     inner_zip.start_file("tl_2019_25_sldl.shx", options).unwrap();
+///     inner_zip.finish().unwrap();
+/// }
+///
+/// let mut outer_buffer = Vec::new();
+/// {
+///     let mut outer_zip = ZipWriter::new(Cursor::new(&mut outer_buffer));
+///     outer_zip.start_file("tl_2019_25_sldl.zip", FileOptions::default()).unwrap();
+///     outer_zip.write_all(&inner_buffer).unwrap();
+///     outer_zip.finish().unwrap();
+/// }
+///
+/// let zip_archive = ZipArchive::new(Cursor::new(outer_buffer)).unwrap();
+/// let result = civicsearch::shapefile_reader_from_zip_archive(zip_archive);
+///
+/// assert!(matches!(result, Err(civicsearch::TIGERShapefileError::InvalidShapefile(_))));
+/// ```
+pub fn shapefile_reader_from_zip_archive<R>(zip_archive: ZipArchive<R>) -> Result<(Reader<impl Read>, Option<String>), TIGERShapefileError>
     where R: Clone + Read + Seek,
 {
     let tiger_shapefile = extract_shapefiles(zip_archive)?;
@@ -250,7 +394,279 @@ pub fn shapefile_reader_from_zip_archive<R>(zip_archive: ZipArchive<R>) -> Resul
     debug!("Adding .shx");
     reader.add_index_source(tiger_shapefile.shapeindex_file)?;
 
-    Ok(reader)
-    
-    
+    Ok((reader, tiger_shapefile.crs_wkt))
+
+
+}
+
+/// Reprojects a point from `source_crs` into `target_crs`.
+///
+/// This always performs a full CRS-to-CRS transform rather than a simple datum shift,
+/// so projected (non-degree) CRSes are handled correctly along with the common
+/// NAD83↔WGS84 geographic case.
+fn reproject_point(point: &geo_types::Point<f64>, source_crs: &str, target_crs: &str) -> Result<geo_types::Point<f64>, TIGERShapefileError> {
+    let transformer = Proj::new_known_crs(source_crs, target_crs, None)?;
+    let (x, y) = transformer.convert((point.x(), point.y()))?;
+    Ok(geo_types::Point::new(x, y))
+}
+
+/// An entry in a district spatial index: a district's bounding box, plus its index
+/// into the original `districts` slice so the exact polygon can be recovered.
+struct DistrictEnvelope {
+    envelope: AABB<[f64; 2]>,
+    index: usize,
+}
+
+impl RTreeObject for DistrictEnvelope {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl PointDistance for DistrictEnvelope {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.envelope.distance_2(point)
+    }
+}
+
+/// Builds an `rstar::RTree` over the axis-aligned bounding box of each district's shape.
+///
+/// Districts with a degenerate or unbounded shape (no `bounding_rect`) are skipped, since
+/// they can't be indexed; in practice this shouldn't happen for real TIGER shapefiles.
+fn build_district_index(districts: &[(Polygon, shapefile::dbase::Record)]) -> RTree<DistrictEnvelope> {
+    let envelopes = districts.iter()
+        .enumerate()
+        .filter_map(|(index, (shape, _))| {
+            let multi_polygon: geo_types::MultiPolygon<f64> = shape.clone().into();
+            let bounding_rect = multi_polygon.bounding_rect()?;
+            Some(DistrictEnvelope {
+                envelope: AABB::from_corners(
+                    [bounding_rect.min().x, bounding_rect.min().y],
+                    [bounding_rect.max().x, bounding_rect.max().y],
+                ),
+                index,
+            })
+        })
+        .collect();
+
+    RTree::bulk_load(envelopes)
+}
+
+/// Finds the closest district to a point that didn't fall inside any district's shape,
+/// along with the distance to it in meters. Used by `--nearest` for points that land
+/// just offshore, on a coastline, or in a data gap. Districts with an indeterminate
+/// closest point (e.g. an empty shape) are skipped.
+///
+/// Rather than scanning every district, this walks `district_index`'s
+/// `nearest_neighbor_iter`, which yields districts in increasing order of
+/// distance-to-bounding-box. That envelope distance is always a lower bound on the
+/// district's true (exact) closest-point distance, so once a candidate's envelope is
+/// farther away than the best exact match found so far, every later candidate must be
+/// farther still and the search stops — bounding the number of (expensive)
+/// `ClosestPoint` checks instead of running one per district nationwide.
+///
+/// `point` is the original WGS84 input point, `reprojected_point` is that same point in
+/// the district shapes' CRS (see [`find_districts_for_points`]), and `back_transformer`
+/// reprojects from that CRS back to WGS84, or `None` if the shapes are themselves in
+/// WGS84. `HaversineDistance` assumes lon/lat degrees, so when the district shapes are
+/// in a projected CRS (e.g. a State Plane or UTM `.prj`, which isn't in degrees at all),
+/// the closest point found on a district's boundary is reprojected back to WGS84 before
+/// the distance is computed — otherwise the "meters" result would silently be
+/// meaningless. The envelope/closest-point pruning above happens in the shapes' own CRS
+/// (before that back-transform), so its units always match the thing it's bounding.
+/// `back_transformer` is built once per [`find_districts_for_points`] call (not once per
+/// point) since constructing a `Proj` isn't free.
+fn find_nearest_district(
+    point: &geo_types::Point<f64>,
+    reprojected_point: &geo_types::Point<f64>,
+    districts: &[(Polygon, shapefile::dbase::Record)],
+    district_index: &RTree<DistrictEnvelope>,
+    fields: &[String],
+    back_transformer: Option<&Proj>,
+) -> Result<Option<(Vec<Option<String>>, f64)>, TIGERShapefileError> {
+    let query = [reprojected_point.x(), reprojected_point.y()];
+    // Tracks the best match found so far, alongside its exact closest-point distance
+    // *in the shapes' own CRS* (not the final WGS84 meters), since that's the unit the
+    // envelope lower bound below is comparable to.
+    let mut best: Option<(Vec<Option<String>>, f64, f64)> = None;
+
+    for candidate in district_index.nearest_neighbor_iter(&query) {
+        if let Some((_, _, best_distance_2)) = &best {
+            if candidate.envelope.distance_2(&query) > *best_distance_2 {
+                break;
+            }
+        }
+
+        let (shape, record) = &districts[candidate.index];
+        let multi_polygon: geo_types::MultiPolygon<f64> = shape.clone().into();
+        let closest_point = match multi_polygon.closest_point(reprojected_point) {
+            Closest::Intersection(p) | Closest::SinglePoint(p) => p,
+            Closest::Indeterminate => continue,
+        };
+        let distance_2 = (closest_point.x() - reprojected_point.x()).powi(2)
+            + (closest_point.y() - reprojected_point.y()).powi(2);
+
+        if let Some((_, _, best_distance_2)) = &best {
+            if distance_2 >= *best_distance_2 {
+                continue;
+            }
+        }
+
+        let closest_point_wgs84 = match back_transformer {
+            Some(transformer) => {
+                let (x, y) = transformer.convert((closest_point.x(), closest_point.y()))?;
+                geo_types::Point::new(x, y)
+            },
+            None => closest_point,
+        };
+        let distance_m = point.haversine_distance(&closest_point_wgs84);
+
+        best = Some((extract_fields(record, fields), distance_m, distance_2));
+    }
+
+    Ok(best.map(|(fields, distance_m, _)| (fields, distance_m)))
+}
+
+/// The result of matching one input point against the district set.
+pub struct PointMatch<'p> {
+    pub point: &'p geo_types::Point<f64>,
+    /// Requested field values for every district whose shape contains `point`, in the
+    /// same order as the `fields` passed to [`find_districts_for_points`].
+    pub matching_districts: Vec<Vec<Option<String>>>,
+    /// Set only when `--nearest` is on and `matching_districts` is empty: the closest
+    /// district's field values, and the distance to it in meters.
+    pub nearest_district: Option<(Vec<Option<String>>, f64)>,
+}
+
+/// For each input point, finds the requested field values of every district whose shape contains it.
+///
+/// `districts` is indexed into an `rstar::RTree` keyed on each district's bounding box,
+/// so only the handful of districts whose envelope contains a point need the precise
+/// (and much more expensive) [`shape_contains_point`] check — this keeps lookups fast
+/// even over national-scale district sets with tens of thousands of polygons. A point
+/// that falls inside more than one district (e.g. overlapping or adjacent polygons)
+/// gets a result for each of them; a point inside none gets an empty `Vec`.
+///
+/// `fields` selects which `.dbf` columns to pull out of each matching district (see
+/// [`extract_fields`]), e.g. `&[String::from("NAMELSAD"), String::from("GEOID")]`.
+/// Each matched district's values are returned in the same order as `fields`.
+///
+/// Input points are assumed to be WGS84 lon/lat, as is typical of a user-supplied CSV.
+/// `target_crs`, if given, is the WKT of the shapefile's actual CRS (see
+/// [`shapefile_reader_from_zip_archive`]) and each point is reprojected into it before
+/// being tested against the district shapes. If `target_crs` is `None` (the archive had
+/// no `.prj` file), points are compared as-is, with a warning, matching the old behavior.
+///
+/// If `nearest` is `true`, a point with no matching district also gets the closest
+/// district and the distance to it (see [`find_nearest_district`]).
+///
+/// # Examples
+/// ```
+/// use shapefile::{Polygon, PolygonRing, Point};
+/// use std::collections::HashMap;
+/// use shapefile::dbase::FieldValue;
+///
+/// let mut record = HashMap::new();
+/// record.insert(String::from("NAMELSAD"), FieldValue::Character(Some(String::from("1st District"))));
+///
+/// let district = (
+///     Polygon::new(PolygonRing::Outer(vec![
+///         Point::new(-1.0, -1.0),
+///         Point::new(-1.0,  1.0),
+///         Point::new( 1.0,  1.0),
+///         Point::new( 1.0, -1.0),
+///     ])),
+///     record,
+/// );
+///
+/// let points = vec![geo_types::Point::new(0.0, 0.0), geo_types::Point::new(5.0, 5.0)];
+/// let districts = vec![district];
+/// let fields = vec![String::from("NAMELSAD")];
+///
+/// let results = civicsearch::find_districts_for_points(points.iter(), &districts, None, &fields, false).unwrap();
+/// assert_eq!(results[0].matching_districts, vec![vec![Some(String::from("1st District"))]]);
+/// assert_eq!(results[1].matching_districts, Vec::<Vec<Option<String>>>::new());
+/// ```
+///
+/// With `target_crs` set, input points are actually reprojected before the `Contains`
+/// check. Here the district is drawn in EPSG:3857 (Web Mercator) meters rather than
+/// WGS84 degrees, so a naive (non-reprojected) comparison would place both input points
+/// outside it:
+/// ```
+/// use shapefile::{Polygon, PolygonRing, Point};
+/// use std::collections::HashMap;
+/// use shapefile::dbase::FieldValue;
+///
+/// let mut record = HashMap::new();
+/// record.insert(String::from("NAMELSAD"), FieldValue::Character(Some(String::from("1st District"))));
+///
+/// let district = (
+///     Polygon::new(PolygonRing::Outer(vec![
+///         Point::new(-200_000.0, -200_000.0),
+///         Point::new(-200_000.0,  200_000.0),
+///         Point::new( 200_000.0,  200_000.0),
+///         Point::new( 200_000.0, -200_000.0),
+///     ])),
+///     record,
+/// );
+///
+/// // (0.0, 0.0) sits at the Web Mercator origin; (45.0, 45.0) reprojects to several
+/// // thousand kilometers away, well outside the district.
+/// let points = vec![geo_types::Point::new(0.0, 0.0), geo_types::Point::new(45.0, 45.0)];
+/// let districts = vec![district];
+/// let fields = vec![String::from("NAMELSAD")];
+///
+/// let results = civicsearch::find_districts_for_points(points.iter(), &districts, Some("EPSG:3857"), &fields, false).unwrap();
+/// assert_eq!(results[0].matching_districts, vec![vec![Some(String::from("1st District"))]]);
+/// assert_eq!(results[1].matching_districts, Vec::<Vec<Option<String>>>::new());
+/// ```
+pub fn find_districts_for_points<'p, P>(
+    points: P,
+    districts: &[(Polygon, shapefile::dbase::Record)],
+    target_crs: Option<&str>,
+    fields: &[String],
+    nearest: bool,
+) -> Result<Vec<PointMatch<'p>>, TIGERShapefileError>
+    where
+        P: Iterator<Item = &'p geo_types::Point<f64>>,
+{
+    let district_index = build_district_index(districts);
+
+    // Built once up front (rather than per-point) for `--nearest`'s WGS84 back-transform,
+    // since every point in the batch shares the same target_crs and Proj::new_known_crs
+    // isn't free.
+    let back_transformer = if nearest {
+        target_crs
+            .map(|crs| Proj::new_known_crs(crs, WGS84_EPSG, None))
+            .transpose()?
+    } else {
+        None
+    };
+
+    points
+        .map(|point| {
+            let reprojected_point = match target_crs {
+                Some(crs) => reproject_point(point, WGS84_EPSG, crs)?,
+                None => {
+                    warn!("No CRS found for shapefile archive; assuming input coordinates already match");
+                    *point
+                },
+            };
+            let matching_districts: Vec<Vec<Option<String>>> = district_index
+                .locate_all_at_point(&[reprojected_point.x(), reprojected_point.y()])
+                .filter(|candidate| shape_contains_point(&districts[candidate.index].0, &reprojected_point))
+                .map(|candidate| extract_fields(&districts[candidate.index].1, fields))
+                .collect();
+
+            let nearest_district = if nearest && matching_districts.is_empty() {
+                find_nearest_district(point, &reprojected_point, districts, &district_index, fields, back_transformer.as_ref())?
+            } else {
+                None
+            };
+
+            Ok(PointMatch { point, matching_districts, nearest_district })
+        })
+        .collect()
 }
\ No newline at end of file
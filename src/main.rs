@@ -5,9 +5,15 @@ use shapefile::Polygon;
 use zip::{ZipArchive};
 use log::{error, info};
 use clap::{Arg, App};
-use serde::{Serialize, Deserialize};
+use serde::Deserialize;
 use std::path::Path;
 use std::ffi::OsStr;
+use std::str::FromStr;
+
+use civicsearch::output::{OutputFormat, PointWriter, build_field_columns};
+
+/// The `.dbf` field(s) pulled out of each matching district when `--fields` isn't given.
+const DEFAULT_FIELDS: &str = "NAMELSAD,GEOID";
 
 #[derive(Debug, Deserialize, Copy, Clone)]
 struct CSVInputRecord {
@@ -15,13 +21,6 @@ struct CSVInputRecord {
     longitude: f64
 }
 
-#[derive(Debug, Clone, Copy, Serialize)]
-struct CSVOutputRecord<'a> {
-    latitude: f64,
-    longitude: f64,
-    district: Option<&'a str>,
-    other_districts: Option<&'a str>,
-}
 fn main() {
     env_logger::init();
 
@@ -44,21 +43,65 @@ fn main() {
             .value_name("SHAPEFILE_ZIP")
             .help("The location of a TIGER shapefile zip file. Please use the whole .zip file and not a .shp")
             .takes_value(true)
-            .required(true)
+            .required_unless("geography")
+        )
+        .arg(Arg::with_name("geography")
+            .short("g")
+            .long("geography")
+            .value_name("GEOGRAPHY")
+            .help("TIGER geography to download instead of passing --shapefile, e.g. 'state', 'county', 'sldl', 'sldu', 'tabblock'")
+            .takes_value(true)
+            .conflicts_with("shapefile")
+        )
+        .arg(Arg::with_name("state")
+            .long("state")
+            .value_name("STATE_FIPS")
+            .help("State FIPS code, required by --geography for state-based geographies (e.g. sldl, sldu, tabblock)")
+            .takes_value(true)
+            .requires("geography")
+        )
+        .arg(Arg::with_name("year")
+            .short("y")
+            .long("year")
+            .value_name("YEAR")
+            .help("TIGER vintage year to download with --geography. Defaults to 2019.")
+            .takes_value(true)
+            .requires("geography")
         )
         .arg(Arg::with_name("output")
             .short("o")
             .long("output")
-            .value_name("OUTPUT_CSV")
-            .value_name("The path for the output CSV (with latitude, longitude, and district columns). out.csv by default.")
+            .value_name("OUTPUT_FILE")
+            .help("The path for the output file (latitude, longitude, and district columns/properties). Defaults to out.csv, or out.geojson with --format geojson.")
+            .takes_value(true)
+        )
+        .arg(Arg::with_name("format")
+            .long("format")
+            .value_name("FORMAT")
+            .help("Output file format: 'csv' (default) or 'geojson'")
+            .takes_value(true)
+        )
+        .arg(Arg::with_name("fields")
+            .short("f")
+            .long("fields")
+            .value_name("FIELDS")
+            .help("Comma-separated .dbf field names to extract from matching districts, e.g. 'NAMELSAD,GEOID,STATEFP'. Defaults to 'NAMELSAD,GEOID'.")
             .takes_value(true)
         )
+        .arg(Arg::with_name("nearest")
+            .long("nearest")
+            .help("For points outside every district, report the nearest district and its distance in meters")
+        )
         .get_matches();
-    
-    // parse path args and convert them into paths
-    let zipfile_path = matches.value_of_os("shapefile")
-        .unwrap_or_else(|| {println!("Could not find path of shapefile."); process::exit(1)});
-    let zipfile_path = Path::new(zipfile_path);
+
+    let fields: Vec<String> = matches.value_of("fields")
+        .unwrap_or(DEFAULT_FIELDS)
+        .split(',')
+        .map(String::from)
+        .collect();
+
+    let output_format = OutputFormat::from_str(matches.value_of("format").unwrap_or("csv"))
+        .unwrap_or_else(|err| {println!("Problem parsing --format: {}", err); process::exit(1)});
 
     let csv_path = matches.value_of_os("input")
         .unwrap_or_else(|| {println!("Could not find path of CSV file."); process::exit(1)});
@@ -76,14 +119,31 @@ fn main() {
     }
     info!("Found {} points in {}", points.len(), csv_path.display());
 
-    // Open shapefile zip file
-    let zipfile_buffer = fs::read(zipfile_path)
-        .unwrap_or_else(|err| {error!("Problem loading zip file: {:?}", err); process::exit(1)});
-    let zipfile_buffer = Cursor::new(zipfile_buffer);
+    // Open shapefile zip file, either from a local path or by downloading (and caching) it
+    let (zipfile_archive, zipfile_source) = match matches.value_of_os("shapefile") {
+        Some(zipfile_path) => {
+            let zipfile_path = Path::new(zipfile_path);
+            let zipfile_buffer = fs::read(zipfile_path)
+                .unwrap_or_else(|err| {error!("Problem loading zip file: {:?}", err); process::exit(1)});
+            let zipfile_archive = ZipArchive::new(Cursor::new(zipfile_buffer))
+                .unwrap_or_else(|err| {error!("Problem reading zip file: {:?}", err); process::exit(1)});
+            (zipfile_archive, zipfile_path.display().to_string())
+        },
+        None => {
+            let geography = matches.value_of("geography")
+                .unwrap_or_else(|| {println!("Could not find geography to download."); process::exit(1)});
+            let state_fips = matches.value_of("state");
+            let year: u32 = matches.value_of("year")
+                .map(|y| y.parse().unwrap_or_else(|err| {error!("Problem parsing --year: {:?}", err); process::exit(1)}))
+                .unwrap_or(2019);
+
+            let zipfile_archive = civicsearch::download::fetch_tiger_archive(geography, state_fips, year)
+                .unwrap_or_else(|err| {error!("Problem downloading shapefile: {:?}", err); process::exit(1)});
+            (zipfile_archive, format!("{} {} ({})", geography, state_fips.unwrap_or("us"), year))
+        },
+    };
 
-    let zipfile_archive = ZipArchive::new(zipfile_buffer)
-        .unwrap_or_else(|err| {error!("Problem reading zip file: {:?}", err); process::exit(1)});
-    let reader = civicsearch::shapefile_reader_from_zip_archive(zipfile_archive)
+    let (reader, crs_wkt) = civicsearch::shapefile_reader_from_zip_archive(zipfile_archive)
         .unwrap_or_else(|err| {error!("Problem parsing shapefile: {:?}", err); process::exit(1)});
 
     let district_map: Vec<(Polygon, shapefile::dbase::Record)> = reader
@@ -91,42 +151,52 @@ fn main() {
         .unwrap_or_else(|err| { error!("Could not read shapefile data: {:?}", err); process::exit(1)})
         .flat_map(|sr| sr.ok())
         .collect();
+
+    info!("Loaded district map with {} districts from {}", district_map.len(), zipfile_source);
     
-    info!("Loaded district map with {} districts from {}", district_map.len(), zipfile_path.display());
-    
-    let points_with_districts = civicsearch::find_districts_for_points(points.iter(), district_map.iter());
+    let nearest = matches.is_present("nearest");
+    let points_with_districts = civicsearch::find_districts_for_points(points.iter(), &district_map, crs_wkt.as_deref(), &fields, nearest)
+        .unwrap_or_else(|err| {error!("Problem reprojecting points: {:?}", err); process::exit(1)});
 
 
-    // Write to CSV
+    // Write output
+    let default_output = match output_format {
+        OutputFormat::Csv => OsStr::new("out.csv"),
+        OutputFormat::GeoJson => OsStr::new("out.geojson"),
+    };
     let output_path = matches.value_of_os("output")
-        .unwrap_or(OsStr::new("out.csv"));
-
+        .unwrap_or(default_output);
     let output_path = Path::new(output_path);
-    let mut wtr = csv::Writer::from_path(output_path)
+
+    let mut writer = PointWriter::new(output_format, output_path)
         .unwrap_or_else(|err| {error!("Problem opening output file: {:?}", err); process::exit(1)});
-    
-    
-    for (point, districts) in &points_with_districts {
-        let (first_district, other_districts): (Option<&String>, Option<&[String]>) = match districts.split_first() {
-            Some((f, o)) if !o.is_empty() => (Some(f), Some(o)),
-            Some((f, _))                  => (Some(f), None),
-            None => (None, None),
-        };
-
-        let other_districts =  match other_districts {
-            Some(o) => Some(o.join(",")),
-            None => None
-        };
-        let record = CSVOutputRecord {
-            latitude: point.y(), 
-            longitude: point.x(), 
-            district: first_district.map(String::as_ref),
-            other_districts: other_districts.as_deref(),
-        };
-        wtr.serialize(record)
-            .unwrap_or_else(|err| {error!("Problem writing record {:?} to file: {:?}", record, err);});
+
+    for point_match in &points_with_districts {
+        let mut field_columns = build_field_columns(&point_match.matching_districts, &fields);
+
+        // Keep nearest_* columns present on every row (not just unmatched ones), since
+        // the CSV/GeoJSON writers expect a consistent column set across all records.
+        if nearest {
+            match &point_match.nearest_district {
+                Some((nearest_values, distance_m)) => {
+                    for (field, value) in fields.iter().zip(nearest_values) {
+                        field_columns.insert(format!("nearest_{}", field), value.clone().unwrap_or_default());
+                    }
+                    field_columns.insert(String::from("nearest_distance_m"), distance_m.to_string());
+                },
+                None => {
+                    for field in &fields {
+                        field_columns.insert(format!("nearest_{}", field), String::new());
+                    }
+                    field_columns.insert(String::from("nearest_distance_m"), String::new());
+                },
+            }
+        }
+
+        writer.write_record(point_match.point, field_columns)
+            .unwrap_or_else(|err| {error!("Problem writing record for point {:?}: {:?}", point_match.point, err);});
     }
 
-    wtr.flush()
-        .unwrap_or_else(|err| { println!("Problem flushing CSV write buffer (this is VERY weird): {:?}", err) });
+    writer.finish()
+        .unwrap_or_else(|err| { println!("Problem flushing output writer (this is VERY weird): {:?}", err) });
 }
@@ -0,0 +1,235 @@
+//! Writers for the enriched point stream produced by [`crate::find_districts_for_points`]:
+//! CSV (the default) and GeoJSON. Both consume the same per-point `(latitude, longitude,
+//! field columns)` data; only how a record hits disk differs.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use geojson::{Feature, FeatureCollection, Geometry, Value as GeoJsonGeometry};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// The output file formats `main` can write enriched points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv,
+    GeoJson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(OutputFormat::Csv),
+            "geojson" => Ok(OutputFormat::GeoJson),
+            other => Err(format!("unknown output format '{}' (expected 'csv' or 'geojson')", other)),
+        }
+    }
+}
+
+/// Errors that can occur writing enriched points to a CSV or GeoJSON output file.
+#[derive(Error, Debug)]
+pub enum OutputError {
+    /// There was a problem writing a CSV record.
+    #[error("error writing CSV output")]
+    Csv(#[from] csv::Error),
+
+    /// There was a problem serializing the GeoJSON `FeatureCollection`.
+    #[error("error writing GeoJSON output")]
+    Json(#[from] serde_json::Error),
+
+    /// There was an IO error opening or writing the output file.
+    #[error("IO error writing output file")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CSVOutputRecord {
+    latitude: f64,
+    longitude: f64,
+    /// One `<field>`/`other_<field>` pair of columns per requested `--fields` entry,
+    /// plus whatever other columns the caller adds (e.g. `main`'s `--nearest` columns).
+    #[serde(flatten)]
+    fields: BTreeMap<String, String>,
+}
+
+/// Builds the `<field>`/`other_<field>` columns for a point, given the requested
+/// `fields` and the field values of every district that matched it: the first matching
+/// district's values go under the fields' own names, and every other matching
+/// district's values are comma-joined under `other_<field>`.
+///
+/// The split is by district, not by field independently — a single district's
+/// requested fields always land together, either all in the first columns or all in the
+/// `other_` columns, even if that district is missing a value for one of them.
+/// Splitting per field instead would let one field's value (e.g. `NAMELSAD`) in the
+/// "first" columns come from a different district than another field's value (e.g.
+/// `GEOID`), silently pairing an unrelated name and code.
+///
+/// # Examples
+/// ```
+/// use civicsearch::output::build_field_columns;
+/// use std::collections::BTreeMap;
+///
+/// let matching_districts = vec![
+///     vec![Some(String::from("1st District")), Some(String::from("2501"))],
+///     vec![Some(String::from("2nd District")), Some(String::from("2502"))],
+/// ];
+/// let fields = vec![String::from("NAMELSAD"), String::from("GEOID")];
+///
+/// let columns = build_field_columns(&matching_districts, &fields);
+///
+/// let mut expected = BTreeMap::new();
+/// expected.insert(String::from("NAMELSAD"), String::from("1st District"));
+/// expected.insert(String::from("other_NAMELSAD"), String::from("2nd District"));
+/// expected.insert(String::from("GEOID"), String::from("2501"));
+/// expected.insert(String::from("other_GEOID"), String::from("2502"));
+///
+/// assert_eq!(columns, expected);
+/// ```
+///
+/// A district missing a value for one field doesn't bleed another district's value
+/// into that field: here the first district has no `GEOID`, but the first columns
+/// still come entirely from the first district rather than borrowing `GEOID` from the
+/// second.
+/// ```
+/// use civicsearch::output::build_field_columns;
+/// use std::collections::BTreeMap;
+///
+/// let matching_districts = vec![
+///     vec![Some(String::from("1st District")), None],
+///     vec![Some(String::from("2nd District")), Some(String::from("2502"))],
+/// ];
+/// let fields = vec![String::from("NAMELSAD"), String::from("GEOID")];
+///
+/// let columns = build_field_columns(&matching_districts, &fields);
+///
+/// let mut expected = BTreeMap::new();
+/// expected.insert(String::from("NAMELSAD"), String::from("1st District"));
+/// expected.insert(String::from("other_NAMELSAD"), String::from("2nd District"));
+/// expected.insert(String::from("GEOID"), String::new());
+/// expected.insert(String::from("other_GEOID"), String::from("2502"));
+///
+/// assert_eq!(columns, expected);
+/// ```
+pub fn build_field_columns(matching_districts: &[Vec<Option<String>>], fields: &[String]) -> BTreeMap<String, String> {
+    let mut field_columns = BTreeMap::new();
+
+    let (first_district, other_districts) = match matching_districts.split_first() {
+        Some(split) => split,
+        None => {
+            for field in fields {
+                field_columns.insert(field.clone(), String::new());
+                field_columns.insert(format!("other_{}", field), String::new());
+            }
+            return field_columns;
+        },
+    };
+
+    for (field_index, field) in fields.iter().enumerate() {
+        let first_value = first_district[field_index].clone().unwrap_or_default();
+        let other_values = other_districts.iter()
+            .filter_map(|d| d[field_index].clone())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        field_columns.insert(field.clone(), first_value);
+        field_columns.insert(format!("other_{}", field), other_values);
+    }
+
+    field_columns
+}
+
+/// Writes enriched points to disk in either CSV or GeoJSON format.
+pub enum PointWriter {
+    Csv(csv::Writer<File>),
+    GeoJson {
+        path: PathBuf,
+        features: Vec<Feature>,
+    },
+}
+
+impl PointWriter {
+    pub fn new(format: OutputFormat, path: &Path) -> Result<Self, OutputError> {
+        Ok(match format {
+            OutputFormat::Csv => PointWriter::Csv(csv::Writer::from_path(path)?),
+            OutputFormat::GeoJson => PointWriter::GeoJson { path: path.to_path_buf(), features: Vec::new() },
+        })
+    }
+
+    /// Writes one point and its `<field>`/`other_<field>` columns (see [`build_field_columns`]).
+    ///
+    /// # Examples
+    /// For `OutputFormat::GeoJson`, each record becomes a `Feature` with a `[lon, lat]`
+    /// `Point` geometry and the field columns flattened into `properties`:
+    /// ```
+    /// use civicsearch::output::{OutputFormat, PointWriter};
+    /// use std::collections::BTreeMap;
+    /// use std::path::Path;
+    ///
+    /// let mut writer = PointWriter::new(OutputFormat::GeoJson, Path::new("unused.geojson")).unwrap();
+    ///
+    /// let point = geo_types::Point::new(-71.06, 42.36);
+    /// let mut field_columns = BTreeMap::new();
+    /// field_columns.insert(String::from("NAMELSAD"), String::from("1st District"));
+    ///
+    /// writer.write_record(&point, field_columns).unwrap();
+    ///
+    /// match &writer {
+    ///     PointWriter::GeoJson { features, .. } => {
+    ///         let geometry = features[0].geometry.as_ref().unwrap();
+    ///         assert_eq!(geometry.value, geojson::Value::Point(vec![-71.06, 42.36]));
+    ///
+    ///         let properties = features[0].properties.as_ref().unwrap();
+    ///         assert_eq!(
+    ///             properties.get("NAMELSAD"),
+    ///             Some(&serde_json::Value::String(String::from("1st District"))),
+    ///         );
+    ///     },
+    ///     _ => panic!("expected a GeoJson writer"),
+    /// }
+    /// ```
+    pub fn write_record(&mut self, point: &geo_types::Point<f64>, field_columns: BTreeMap<String, String>) -> Result<(), OutputError> {
+        match self {
+            PointWriter::Csv(writer) => {
+                let record = CSVOutputRecord {
+                    latitude: point.y(),
+                    longitude: point.x(),
+                    fields: field_columns,
+                };
+                writer.serialize(&record)?;
+            },
+            PointWriter::GeoJson { features, .. } => {
+                let geometry = Geometry::new(GeoJsonGeometry::Point(vec![point.x(), point.y()]));
+                let mut properties = Map::new();
+                for (key, value) in field_columns {
+                    properties.insert(key, Value::String(value));
+                }
+                features.push(Feature {
+                    bbox: None,
+                    geometry: Some(geometry),
+                    id: None,
+                    properties: Some(properties),
+                    foreign_members: None,
+                });
+            },
+        }
+        Ok(())
+    }
+
+    /// Flushes (CSV) or serializes the accumulated `FeatureCollection` (GeoJSON) to disk.
+    pub fn finish(self) -> Result<(), OutputError> {
+        match self {
+            PointWriter::Csv(mut writer) => Ok(writer.flush()?),
+            PointWriter::GeoJson { path, features } => {
+                let collection = FeatureCollection { bbox: None, features, foreign_members: None };
+                let file = File::create(path)?;
+                serde_json::to_writer(file, &collection)?;
+                Ok(())
+            },
+        }
+    }
+}